@@ -1,10 +1,16 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use comrak::{format_html_with_plugins, parse_document, Arena, ComrakPlugins};
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageFormat};
 use lazy_static::lazy_static;
 use rocket::{
-    fs::NamedFile,
-    http::{uri::Origin, Status},
+    form::FromForm,
+    http::{uri::Origin, ContentType, Status},
     route::Route,
     State,
 };
@@ -14,7 +20,12 @@ use crate::{
     guards::Cluster,
     responses::{ResponseOk, Template},
     templates::docs::*,
-    utils::{config, markdown},
+    utils::{
+        config,
+        content_source::{ContentSource, LocalFs, S3},
+        markdown,
+        ttl_cache::TtlCache,
+    },
     components::cms::index_link::IndexLink
 };
 
@@ -25,46 +36,387 @@ lazy_static! {
 }
 
 
+/// How a resized image should fill the requested `w`x`h` box.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Fit {
+    Cover,
+    Contain,
+    Scale,
+}
+
+impl Fit {
+    fn parse(s: Option<&str>) -> Fit {
+        match s {
+            Some("contain") => Fit::Contain,
+            Some("scale") => Fit::Scale,
+            _ => Fit::Cover,
+        }
+    }
+}
+
+/// Query params accepted on an asset route, e.g. `?w=800&h=600&fit=cover&format=webp`.
+#[derive(Debug, Clone, Default, FromForm)]
+pub struct ImageParams {
+    pub w: Option<u32>,
+    pub h: Option<u32>,
+    pub fit: Option<String>,
+    pub format: Option<String>,
+}
+
+impl ImageParams {
+    fn is_empty(&self) -> bool {
+        self.w.is_none() && self.h.is_none() && self.fit.is_none() && self.format.is_none()
+    }
+
+    /// Cache key for a derived variant: source path + content + the params that affect it.
+    /// Hashing the bytes themselves (rather than an mtime) lets the cache work
+    /// the same whether the original came from local disk or an S3 bucket.
+    fn cache_key(&self, source: &str, bytes: &[u8]) -> String {
+        let mut hasher = DefaultHasher::new();
+        source.hash(&mut hasher);
+        bytes.hash(&mut hasher);
+        self.w.hash(&mut hasher);
+        self.h.hash(&mut hasher);
+        self.fit.hash(&mut hasher);
+        self.format.hash(&mut hasher);
+        format!("{:x}", hasher.finish())
+    }
+}
+
+fn image_format_from_ext(ext: &str) -> ImageFormat {
+    match ext {
+        "webp" => ImageFormat::WebP,
+        "png" => ImageFormat::Png,
+        _ => ImageFormat::Jpeg,
+    }
+}
+
+/// Content-Type for an asset, from its file extension. Assets are served as
+/// raw bytes now that they may come from S3, so Rocket no longer infers this
+/// from an on-disk `NamedFile` for us.
+fn content_type_of(path: &str) -> ContentType {
+    Path::new(path)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .and_then(ContentType::from_extension)
+        .unwrap_or(ContentType::Binary)
+}
+
+/// `fit` only makes sense once both dimensions of the target box are known:
+/// with only one of `w`/`h` given, there's nothing to crop or letterbox
+/// against, so we always scale proportionally on that one axis instead of
+/// defaulting to a `Cover` center-crop at the source's full other dimension.
+fn resize(image: DynamicImage, w: Option<u32>, h: Option<u32>, fit: Fit) -> DynamicImage {
+    match (w, h) {
+        (Some(w), None) => image.resize(w, u32::MAX, FilterType::Lanczos3),
+        (None, Some(h)) => image.resize(u32::MAX, h, FilterType::Lanczos3),
+        (None, None) => image,
+        (Some(w), Some(h)) => match fit {
+            Fit::Scale => image.resize_exact(w, h, FilterType::Lanczos3),
+            Fit::Contain => image.resize(w, h, FilterType::Lanczos3),
+            Fit::Cover => image.resize_to_fill(w, h, FilterType::Lanczos3),
+        },
+    }
+}
+
+/// Front matter read from the YAML block at the top of a CMS document.
+#[derive(Debug, Clone, Default)]
+struct FrontMatter {
+    image: Option<String>,
+    description: Option<String>,
+    tags: Vec<String>,
+    author: Option<String>,
+    date: Option<String>,
+}
+
+/// Splits `contents` into its front matter (if any) and the remaining markdown body.
+fn parse_front_matter(contents: &str) -> (FrontMatter, String) {
+    let parts = contents.split("---").collect::<Vec<&str>>();
+    if parts.len() <= 1 {
+        return (FrontMatter::default(), contents.to_string());
+    }
+
+    match YamlLoader::load_from_str(parts[1]) {
+        Ok(docs) if !docs.is_empty() => {
+            let meta = docs[0].clone();
+            if meta.as_hash().is_none() {
+                return (FrontMatter::default(), contents.to_string());
+            }
+
+            let string_field = |key: &str| -> Option<String> {
+                match meta[key].is_badvalue() {
+                    true => None,
+                    false => Some(meta[key].as_str().unwrap_or_default().to_string()),
+                }
+            };
+
+            let tags = match meta["tags"].as_vec() {
+                Some(tags) => tags
+                    .iter()
+                    .filter_map(|tag| tag.as_str().map(String::from))
+                    .collect(),
+                None => vec![],
+            };
+
+            let front_matter = FrontMatter {
+                image: string_field("image"),
+                description: string_field("description"),
+                tags,
+                author: string_field("author"),
+                date: string_field("date"),
+            };
+
+            (front_matter, parts[2..].join("---"))
+        }
+        _ => (FrontMatter::default(), contents.to_string()),
+    }
+}
+
+/// A document's entry in a collection's tag/author taxonomy index.
+#[derive(Debug, Clone)]
+struct DocMeta {
+    title: String,
+    href: String,
+    description: Option<String>,
+    image: Option<String>,
+    date: Option<String>,
+    tags: Vec<String>,
+    author: Option<String>,
+}
+
+/// Per-collection tag/author taxonomy and date-descending post list, built
+/// once at load time by scanning every markdown file's front matter.
+#[derive(Debug, Clone, Default)]
+struct Taxonomy {
+    docs: Vec<DocMeta>,
+    tags: HashMap<String, Vec<DocMeta>>,
+    authors: HashMap<String, Vec<DocMeta>>,
+}
+
+impl Taxonomy {
+    /// Reads title + front matter out of every markdown file a `ContentSource`
+    /// lists (skipping `SUMMARY.md`), and groups them by tag and author.
+    async fn build(source: &dyn ContentSource, url_root: &Path) -> Taxonomy {
+        let mut taxonomy = Taxonomy::default();
+
+        let files = source.list_markdown().await.unwrap_or_default();
+        for relative_path in files {
+            let Ok(contents) = source.read_markdown(&relative_path).await else {
+                continue;
+            };
+            let (front_matter, body) = parse_front_matter(&contents);
+
+            let arena = Arena::new();
+            let root = parse_document(&arena, &body, &markdown::options());
+            let title = markdown::get_title(&root).unwrap_or_else(|| {
+                relative_path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or_default()
+                    .to_string()
+            });
+
+            let href = url_root
+                .join(relative_path.with_extension(""))
+                .to_string_lossy()
+                .to_string();
+
+            taxonomy.docs.push(DocMeta {
+                title,
+                href,
+                description: front_matter.description,
+                image: front_matter.image,
+                date: front_matter.date,
+                tags: front_matter.tags,
+                author: front_matter.author,
+            });
+        }
+
+        for doc in &taxonomy.docs {
+            for tag in &doc.tags {
+                taxonomy.tags.entry(tag.clone()).or_default().push(doc.clone());
+            }
+            if let Some(author) = &doc.author {
+                taxonomy.authors.entry(author.clone()).or_default().push(doc.clone());
+            }
+        }
+
+        taxonomy.docs.sort_by(|a, b| b.date.cmp(&a.date));
+        for docs in taxonomy.tags.values_mut().chain(taxonomy.authors.values_mut()) {
+            docs.sort_by(|a, b| b.date.cmp(&a.date));
+        }
+
+        taxonomy
+    }
+}
+
 /// A Gitbook collection of documents
 struct Collection {
     name: String,
-    asset_dir: PathBuf,
+    /// Where markdown/assets actually live: local disk, or an S3 bucket when
+    /// `CMS_S3_BUCKET` is configured for this collection.
+    source: Box<dyn ContentSource>,
+    /// In-process cache of hot documents, so a `ContentSource` backed by S3
+    /// isn't hit on every request.
+    markdown_cache: TtlCache<String>,
+    asset_cache: TtlCache<Vec<u8>>,
     index: Vec<IndexLink>,
+    taxonomy: Taxonomy,
+    /// False for collections (the blog) with no curated SUMMARY.md; their
+    /// root route renders a post listing instead of a README.
+    has_summary: bool,
 }
 
 impl Collection {
     pub fn new(name: &str) -> Collection {
         info!("Loading content: {name}");
-        let root_dir = PathBuf::from(name.to_string().to_lowercase());
-
-        let index_path = config::cms_dir()
-            .join(&root_dir)
-            .join("SUMMARY.md");
-        let contents = std::fs::read_to_string(&index_path).expect(
-            format!(
-                "could not read table of contents markdown: {:?}",
-                &index_path
-            )
-                .as_str(),
-        );
-        let mdast = ::markdown::to_mdast(&contents, &::markdown::ParseOptions::default())
-            .expect("could not parse table of contents markdown");
-        let url = PathBuf::from("/").join(name.to_lowercase());
-        let index = markdown::parse_summary_into_nav_links(&mdast, &url)
-            .expect("could not extract nav links from table of contents");
+        let collection_key = name.to_lowercase();
+        let url = PathBuf::from("/").join(&collection_key);
+
+        let source: Box<dyn ContentSource> = match config::s3_config(&collection_key) {
+            Some(s3) => Box::new(
+                S3::new(&s3.endpoint, &s3.region, &s3.bucket, &s3.prefix)
+                    .expect("could not connect to S3 content bucket"),
+            ),
+            None => Box::new(LocalFs::new(config::cms_dir().join(&collection_key))),
+        };
+
+        // `new()` runs once, the first time something dereferences this
+        // `lazy_static!`. `init_collections()` forces that to happen eagerly while
+        // Rocket is still being assembled, so this blocking load never runs
+        // lazily from inside a request handler, where it would block a
+        // Tokio worker thread (and risk a pool-wide stall under concurrent
+        // first-hits) on top of a runtime that's already serving requests.
+        let taxonomy = futures::executor::block_on(Taxonomy::build(source.as_ref(), &url));
+
+        // Collections curated with a SUMMARY.md (docs, careers) get their nav
+        // order from it. Collections without one (the blog) fall back to an
+        // index built from front matter, newest post first.
+        let (index, has_summary) = match futures::executor::block_on(source.read_summary()) {
+            Ok(contents) => {
+                let mdast = ::markdown::to_mdast(&contents, &::markdown::ParseOptions::default())
+                    .expect("could not parse table of contents markdown");
+                let index = markdown::parse_summary_into_nav_links(&mdast, &url)
+                    .expect("could not extract nav links from table of contents");
+                (index, true)
+            }
+            Err(_) => {
+                let index = taxonomy
+                    .docs
+                    .iter()
+                    .map(|doc| IndexLink::new(&doc.title).href(&doc.href))
+                    .collect();
+                (index, false)
+            }
+        };
 
         Collection {
             name: name.to_string(),
-            asset_dir: config::cms_dir()
-                .join(&root_dir)
-                .join(".gitbook")
-                .join("assets"),
-            index
+            source,
+            markdown_cache: TtlCache::new(Duration::from_secs(60)),
+            asset_cache: TtlCache::new(Duration::from_secs(60)),
+            index,
+            taxonomy,
+            has_summary,
         }
     }
-    pub async fn get_asset(&self, path: &str) -> Option<NamedFile> {
+
+    /// Fetches a document's markdown, via the cache when it's still fresh.
+    async fn read_markdown(&self, path: &Path) -> anyhow::Result<String> {
+        let key = path.to_path_buf();
+        if let Some(contents) = self.markdown_cache.get(&key) {
+            return Ok(contents);
+        }
+
+        let contents = self.source.read_markdown(path).await?;
+        self.markdown_cache.put(key, contents.clone());
+        Ok(contents)
+    }
+
+    /// Fetches an asset's bytes, via the cache when it's still fresh.
+    async fn read_asset(&self, path: &Path) -> anyhow::Result<Vec<u8>> {
+        let key = path.to_path_buf();
+        if let Some(bytes) = self.asset_cache.get(&key) {
+            return Ok(bytes);
+        }
+
+        let bytes = self.source.read_asset(path).await?;
+        self.asset_cache.put(key, bytes.clone());
+        Ok(bytes)
+    }
+
+    pub async fn get_asset(&self, path: &str, params: ImageParams) -> Option<(ContentType, Vec<u8>)> {
         info!("get_asset: {} {path}", self.name);
-        NamedFile::open(self.asset_dir.join(path)).await.ok()
+        let bytes = self.read_asset(Path::new(path)).await.ok()?;
+
+        if params.is_empty() {
+            return Some((content_type_of(path), bytes));
+        }
+
+        match self.resized_asset(path, &bytes, &params).await {
+            Some(variant) => Some(variant),
+            None => Some((content_type_of(path), bytes)),
+        }
+    }
+
+    /// Resizes `bytes` per `params`, caching the result on disk keyed by
+    /// (source path, content, params) so repeat requests skip re-encoding.
+    async fn resized_asset(
+        &self,
+        source: &str,
+        bytes: &[u8],
+        params: &ImageParams,
+    ) -> Option<(ContentType, Vec<u8>)> {
+        let format = params.format.clone().unwrap_or_else(|| "jpg".to_string());
+        let cache_dir = config::cms_dir().join(".cache").join("images");
+        let cache_path = cache_dir.join(format!("{}.{format}", params.cache_key(source, bytes)));
+        let content_type = content_type_of(&format!("variant.{format}"));
+
+        if let Ok(cached) = tokio::fs::read(&cache_path).await {
+            return Some((content_type, cached));
+        }
+
+        let bytes = bytes.to_vec();
+        let cache_path_clone = cache_path.clone();
+        let fit = Fit::parse(params.fit.as_deref());
+        let (w, h) = (params.w, params.h);
+        let format_for_encode = format.clone();
+
+        let resized = tokio::task::spawn_blocking(move || -> Result<Vec<u8>, image::ImageError> {
+            let image = image::load_from_memory(&bytes)?;
+            let resized = resize(image, w, h, fit);
+            let mut buf = std::io::Cursor::new(vec![]);
+            resized.write_to(&mut buf, image_format_from_ext(&format_for_encode))?;
+            Ok(buf.into_inner())
+        })
+        .await
+        .ok()?
+        .ok()?;
+
+        if let Some(parent) = cache_path_clone.parent() {
+            let _ = tokio::fs::create_dir_all(parent).await;
+        }
+        let _ = tokio::fs::write(&cache_path_clone, &resized).await;
+
+        Some((content_type, resized))
+    }
+
+    /// Given an asset path and a list of target widths, returns the
+    /// `?w=<width>` variant URLs so callers (e.g. the shortcode layer) can
+    /// emit a responsive `srcset`.
+    pub fn asset_variant_urls(&self, path: &str, widths: &[u32]) -> Vec<(u32, String)> {
+        widths
+            .iter()
+            .map(|w| {
+                (
+                    *w,
+                    format!(
+                        "/{}/.gitbook/assets/{path}?w={w}",
+                        self.name.to_lowercase()
+                    ),
+                )
+            })
+            .collect()
     }
 
     pub async fn get_content(&self, mut path: PathBuf, cluster: &Cluster, origin: &Origin<'_>) -> Result<ResponseOk, Status> {
@@ -98,18 +450,22 @@ impl Collection {
             .to_string();
         let url = path.clone();
         info!("path: {:?} | folder: {:?}", path, self.name);
+
+        // Collections without a curated SUMMARY.md (the blog) have no README
+        // to fall back to; serve the front-matter-driven post listing instead.
+        if !self.has_summary && (path.is_empty() || path == "/") {
+            return self.render_post_listing(cluster).await;
+        }
+
         if path.ends_with("/") || path.is_empty() {
             path.push_str("README");
         }
 
         // Get the document content
-        let path = config::cms_dir()
-            .join(self.name.to_lowercase())
-            .join(path.to_string() + ".md");
+        let path = PathBuf::from(path.to_string() + ".md");
         info!("path: {:?}", path);
 
-        // Read to string
-        let contents = match tokio::fs::read_to_string(&path).await {
+        let contents = match self.read_markdown(&path).await {
             Ok(contents) => {
                 info!("loading markdown file: '{:?}", path);
                 contents
@@ -119,47 +475,40 @@ impl Collection {
                 return Err(Status::NotFound);
             }
         };
-        let parts = contents.split("---").collect::<Vec<&str>>();
-        let ((image, description), contents) = if parts.len() > 1 {
-            match YamlLoader::load_from_str(parts[1]) {
-                Ok(meta) => {
-                    if !meta.is_empty() {
-                        let meta = meta[0].clone();
-                        if meta.as_hash().is_none() {
-                            ((None, None), contents.to_string())
-                        } else {
-                            let description: Option<String> = match meta["description"].is_badvalue() {
-                                true => None,
-                                false => Some(meta["description"].as_str().unwrap().to_string()),
-                            };
-
-                            let image: Option<String> = match meta["image"].is_badvalue() {
-                                true => None,
-                                false => Some(meta["image"].as_str().unwrap().to_string()),
-                            };
-
-                            ((image, description), parts[2..].join("---").to_string())
-                        }
-                    } else {
-                        ((None, None), contents.to_string())
-                    }
-                }
-                Err(_) => ((None, None), contents.to_string()),
-            }
-        } else {
-            ((None, None), contents.to_string())
-        };
+        let (front_matter, contents) = parse_front_matter(&contents);
+        let FrontMatter {
+            image,
+            description,
+            tags,
+            author: _,
+            date: _,
+        } = front_matter;
+
+        // Expand {{ shortcode(...) }} / {% shortcode(...) %}...{% end %} before parsing,
+        // so the resulting HTML fragments flow through comrak like any other raw HTML.
+        let contents = markdown::expand_shortcodes(&contents).unwrap_or(contents);
 
         // Parse Markdown
         let arena = Arena::new();
         let root = parse_document(&arena, &contents, &markdown::options());
 
-        // Title of the document is the first (and typically only) <h1>
-        let title = markdown::get_title(&root).unwrap();
+        // Title of the document is the first (and typically only) <h1>,
+        // falling back to the file stem for posts that only carry a title in
+        // front matter, same as `Taxonomy::build`.
+        let title = markdown::get_title(&root).unwrap_or_else(|| {
+            path.file_stem()
+                .and_then(|s| s.to_str())
+                .unwrap_or_default()
+                .to_string()
+        });
         let toc_links = markdown::get_toc(&root).unwrap();
 
         markdown::wrap_tables(&root, &arena).unwrap();
 
+        // Fenced blocks using linenos/hl_lines/diff need markup the syntax
+        // highlighter adapter can't validly emit inside comrak's <pre><code>.
+        markdown::highlight_extended_code_blocks(&root, &arena).unwrap();
+
         // MkDocs syntax support, e.g. tabs, notes, alerts, etc.
         markdown::mkdocs(&root, &arena).unwrap();
 
@@ -201,6 +550,106 @@ impl Collection {
             .nav_title(&self.name)
             .nav_links(&index)
             .toc_links(&toc_links)
+            .tags(&tags)
+            .footer(cluster.context.marketing_footer.to_string());
+
+        Ok(ResponseOk(
+            layout.render(crate::templates::Article { content: html }),
+        ))
+    }
+
+    /// Renders the collection root (`/blog`) as a card listing of every post,
+    /// newest first, for collections with no curated SUMMARY.md.
+    async fn render_post_listing(&self, cluster: &Cluster) -> Result<ResponseOk, Status> {
+        let mut html = String::new();
+        for doc in &self.taxonomy.docs {
+            let image = match &doc.image {
+                Some(image) => format!(r#"<img src="{image}" alt="{}">"#, doc.title),
+                None => String::new(),
+            };
+            html.push_str(&format!(
+                r#"<a class="post-listing-card" href="{href}">
+                    {image}
+                    <h3>{title}</h3>
+                    <p class="date">{date}</p>
+                    <p class="description">{description}</p>
+                </a>"#,
+                href = doc.href,
+                title = doc.title,
+                date = doc.date.as_deref().unwrap_or_default(),
+                description = doc.description.as_deref().unwrap_or_default(),
+            ));
+        }
+
+        let user = if cluster.context.user.is_anonymous() {
+            None
+        } else {
+            Some(cluster.context.user.clone())
+        };
+
+        let mut layout = crate::templates::Layout::new(&self.name);
+        if let Some(user) = user {
+            layout.user(&user);
+        }
+
+        let layout = layout
+            .nav_title(&self.name)
+            .nav_links(&self.index)
+            .footer(cluster.context.marketing_footer.to_string());
+
+        Ok(ResponseOk(
+            layout.render(crate::templates::Article { content: html }),
+        ))
+    }
+
+    /// Renders a listing page (title, description, date, link) of every
+    /// document tagged with `key`, or written by `key` when `kind == "authors"`.
+    async fn render_taxonomy_listing(
+        &self,
+        kind: &str,
+        key: &str,
+        cluster: &Cluster,
+    ) -> Result<ResponseOk, Status> {
+        let docs = match kind {
+            "tags" => self.taxonomy.tags.get(key),
+            "authors" => self.taxonomy.authors.get(key),
+            _ => None,
+        };
+
+        let docs = match docs {
+            Some(docs) => docs,
+            None => return Err(Status::NotFound),
+        };
+
+        let mut html = String::new();
+        for doc in docs {
+            html.push_str(&format!(
+                r#"<a class="taxonomy-listing-item" href="{href}">
+                    <h3>{title}</h3>
+                    <p class="date">{date}</p>
+                    <p class="description">{description}</p>
+                </a>"#,
+                href = doc.href,
+                title = doc.title,
+                date = doc.date.as_deref().unwrap_or_default(),
+                description = doc.description.as_deref().unwrap_or_default(),
+            ));
+        }
+
+        let user = if cluster.context.user.is_anonymous() {
+            None
+        } else {
+            Some(cluster.context.user.clone())
+        };
+
+        let mut layout = crate::templates::Layout::new(&format!("{kind}: {key}"));
+        if let Some(user) = user {
+            layout.user(&user);
+        }
+
+        let layout = layout
+            .nav_title(&self.name)
+            .nav_links(&self.index)
             .footer(cluster.context.marketing_footer.to_string());
 
         Ok(ResponseOk(
@@ -222,14 +671,19 @@ async fn search(query: &str, index: &State<markdown::SearchIndex>) -> ResponseOk
     )
 }
 
-#[get("/careers/.gitbook/assets/<path>", rank = 10)]
-pub async fn get_careers_asset(path: &str) -> Option<NamedFile> {
-    CAREERS.get_asset(path).await
+#[get("/blog/.gitbook/assets/<path>?<params..>", rank = 10)]
+pub async fn get_blog_asset(path: &str, params: ImageParams) -> Option<(ContentType, Vec<u8>)> {
+    BLOG.get_asset(path, params).await
 }
 
-#[get("/docs/.gitbook/assets/<path>", rank = 10)]
-pub async fn get_docs_asset(path: &str) -> Option<NamedFile> {
-    DOCS.get_asset(path).await
+#[get("/careers/.gitbook/assets/<path>?<params..>", rank = 10)]
+pub async fn get_careers_asset(path: &str, params: ImageParams) -> Option<(ContentType, Vec<u8>)> {
+    CAREERS.get_asset(path, params).await
+}
+
+#[get("/docs/.gitbook/assets/<path>?<params..>", rank = 10)]
+pub async fn get_docs_asset(path: &str, params: ImageParams) -> Option<(ContentType, Vec<u8>)> {
+    DOCS.get_asset(path, params).await
 }
 
 #[get("/careers/<path..>", rank = 5)]
@@ -250,77 +704,44 @@ async fn get_docs(
     DOCS.get_content(path, cluster, origin).await
 }
 
-#[get("/blog/<path..>", rank = 10)]
-async fn get_blog<'a>(path: PathBuf, cluster: &Cluster) -> Result<ResponseOk, Status> {
-    todo!()
-    // render(
-    //     cluster,
-    //     &path,
-    //     vec![
-    //         NavLink::new("Speeding up vector recall by 5x with HNSW")
-    //             .href("/blog/speeding-up-vector-recall-by-5x-with-hnsw"),
-    //         NavLink::new("How-to Improve Search Results with Machine Learning")
-    //             .href("/blog/how-to-improve-search-results-with-machine-learning"),
-    //         NavLink::new("pgml-chat: A command-line tool for deploying low-latency knowledge-based chatbots: Part I")
-    //             .href("/blog/pgml-chat-a-command-line-tool-for-deploying-low-latency-knowledge-based-chatbots-part-I"),
-    //         NavLink::new("Announcing support for AWS us-east-1 region")
-    //             .href("/blog/announcing-support-for-aws-us-east-1-region"),
-    //         NavLink::new("LLM based pipelines with PostgresML and dbt (data build tool)")
-    //             .href("/blog/llm-based-pipelines-with-postgresml-and-dbt"),
-    //         NavLink::new("How we generate JavaScript and Python SDKs from our canonical Rust SDK")
-    //             .href("/blog/how-we-generate-javascript-and-python-sdks-from-our-canonical-rust-sdk"),
-    //         NavLink::new("Announcing GPTQ & GGML Quantized LLM support for Huggingface Transformers")
-    //             .href("/blog/announcing-gptq-and-ggml-quantized-llm-support-for-huggingface-transformers"),
-    //         NavLink::new("Making Postgres 30 Percent Faster in Production")
-    //             .href("/blog/making-postgres-30-percent-faster-in-production"),
-    //         NavLink::new("MindsDB vs PostgresML")
-    //             .href("/blog/mindsdb-vs-postgresml"),
-    //         NavLink::new("Introducing PostgresML Python SDK: Build End-to-End Vector Search Applications without OpenAI and Pinecone")
-    //             .href("/blog/introducing-postgresml-python-sdk-build-end-to-end-vector-search-applications-without-openai-and-pinecone"),
-    //         NavLink::new("PostgresML raises $4.7M to launch serverless AI application databases based on Postgres")
-    //             .href("/blog/postgresml-raises-4.7M-to-launch-serverless-ai-application-databases-based-on-postgres"),
-    //         NavLink::new("PG Stat Sysinfo, a Postgres Extension for Querying System Statistics")
-    //             .href("/blog/pg-stat-sysinfo-a-pg-extension"),
-    //         NavLink::new("PostgresML as a memory backend to Auto-GPT")
-    //             .href("/blog/postgresml-as-a-memory-backend-to-auto-gpt"),
-    //         NavLink::new("Personalize embedding search results with Huggingface and pgvector")
-    //             .href(
-    //             "/blog/personalize-embedding-vector-search-results-with-huggingface-and-pgvector",
-    //         ),
-    //         NavLink::new("Tuning vector recall while generating query embeddings in the database")
-    //             .href(
-    //                 "/blog/tuning-vector-recall-while-generating-query-embeddings-in-the-database",
-    //             ),
-    //         NavLink::new("Generating LLM embeddings with open source models in PostgresML")
-    //             .href("/blog/generating-llm-embeddings-with-open-source-models-in-postgresml"),
-    //         NavLink::new("Scaling PostgresML to 1 Million Requests per Second")
-    //             .href("/blog/scaling-postgresml-to-one-million-requests-per-second"),
-    //         NavLink::new("PostgresML is 8-40x faster than Python HTTP Microservices")
-    //             .href("/blog/postgresml-is-8x-faster-than-python-http-microservices"),
-    //         NavLink::new("Backwards Compatible or Bust: Python Inside Rust Inside Postgres")
-    //             .href("/blog/backwards-compatible-or-bust-python-inside-rust-inside-postgres"),
-    //         NavLink::new("PostresML is Moving to Rust for our 2.0 Release")
-    //             .href("/blog/postgresml-is-moving-to-rust-for-our-2.0-release"),
-    //         NavLink::new("Which Database, That is the Question")
-    //             .href("/blog/which-database-that-is-the-question"),
-    //         NavLink::new("Postgres Full Text Search is Awesome")
-    //             .href("/blog/postgres-full-text-search-is-awesome"),
-    //         NavLink::new("Oxidizing Machine Learning").href("/blog/oxidizing-machine-learning"),
-    //         NavLink::new("Data is Living and Relational")
-    //             .href("/blog/data-is-living-and-relational"),
-    //     ],
-    //     "Blog",
-    //     &Path::new("blog"),
-    //     config::blogs_dir(),
-    // )
-    // .await
+#[get("/blog/tags/<tag>", rank = 4)]
+async fn get_blog_tag(tag: &str, cluster: &Cluster) -> Result<ResponseOk, Status> {
+    BLOG.render_taxonomy_listing("tags", tag, cluster).await
+}
+
+#[get("/blog/authors/<name>", rank = 4)]
+async fn get_blog_author(name: &str, cluster: &Cluster) -> Result<ResponseOk, Status> {
+    BLOG.render_taxonomy_listing("authors", name, cluster).await
+}
+
+#[get("/blog/<path..>", rank = 5)]
+async fn get_blog(
+    path: PathBuf,
+    cluster: &Cluster,
+    origin: &Origin<'_>,
+) -> Result<ResponseOk, Status> {
+    BLOG.get_content(path, cluster, origin).await
 }
 
 
 
+/// Forces `BLOG`/`DOCS`/`CAREERS` to load now, while Rocket is still being
+/// assembled and before it starts accepting requests, rather than lazily on
+/// whichever collection's first request happens to land first.
+fn init_collections() {
+    lazy_static::initialize(&BLOG);
+    lazy_static::initialize(&DOCS);
+    lazy_static::initialize(&CAREERS);
+}
+
 pub fn routes() -> Vec<Route> {
+    init_collections();
+
     routes![
         get_blog,
+        get_blog_tag,
+        get_blog_author,
+        get_blog_asset,
         get_careers,
         get_careers_asset,
         get_docs,
@@ -334,6 +755,26 @@ mod test {
     use super::*;
     use crate::utils::markdown::{options, MarkdownHeadings, SyntaxHighlighter};
 
+    #[test]
+    fn test_resize_width_only_is_proportional_not_cropped() {
+        let image = DynamicImage::new_rgb8(400, 200);
+
+        let resized = resize(image, Some(800), None, Fit::Cover);
+
+        // A 400x200 source scaled to width 800 should stay 2:1, i.e. height
+        // 400 - not resize_to_fill's center-cropped height=200.
+        assert_eq!((resized.width(), resized.height()), (800, 400));
+    }
+
+    #[test]
+    fn test_resize_both_dims_cover_still_crops() {
+        let image = DynamicImage::new_rgb8(400, 200);
+
+        let resized = resize(image, Some(300), Some(300), Fit::Cover);
+
+        assert_eq!((resized.width(), resized.height()), (300, 300));
+    }
+
     #[test]
     fn test_syntax_highlighting() {
         let code = r#"
@@ -357,7 +798,103 @@ SELECT * FROM test;
         format_html_with_plugins(root, &options(), &mut html, &plugins).unwrap();
         let html = String::from_utf8(html).unwrap();
 
-        assert!(html.contains("<span class=\"syntax-highlight\">SELECT</span>"));
+        assert!(html.contains(r#"<pre class="syntax-highlight">"#));
+        assert!(html.contains(r#"<code class="language-postgresql">"#));
+        assert!(html.contains("SELECT"));
+
+        // comrak wraps the adapter's output in <pre><code>...</code></pre>
+        // itself; write_highlighted must not add another layer of either.
+        assert_eq!(html.matches("<pre").count(), 1);
+        assert_eq!(html.matches("<code").count(), 1);
+    }
+
+    #[test]
+    fn test_syntax_highlighting_linenos_not_nested_in_code() {
+        let code = r#"
+```postgresql,linenos
+SELECT * FROM test;
+```
+        "#;
+
+        let arena = Arena::new();
+        let root = parse_document(&arena, &code, &options());
+
+        markdown::highlight_extended_code_blocks(&root, &arena).unwrap();
+
+        let plugins = ComrakPlugins::default();
+        let mut html = vec![];
+        format_html_with_plugins(root, &options(), &mut html, &plugins).unwrap();
+        let html = String::from_utf8(html).unwrap();
+
+        assert!(html.contains(r#"<table class="syntax-highlight-lines language-postgresql">"#));
+        // The line-gutter table replaces the fenced block entirely, rather
+        // than nesting inside comrak's <pre><code> wrapper.
+        assert!(!html.contains("<pre"));
+        assert!(!html.contains("<code"));
+    }
+
+    #[test]
+    fn test_syntax_highlighting_hl_lines_space_separated() {
+        // `highlight_extended_code_blocks` parses fence options off the AST's
+        // own `info` field, not the `lang` comrak's highlighter adapter
+        // receives (which is truncated at the first whitespace) — so a
+        // space-separated `hl_lines` list like this one isn't silently
+        // dropped.
+        let code = r#"
+```postgresql,hl_lines=1-3 5
+SELECT 1;
+SELECT 2;
+SELECT 3;
+SELECT 4;
+SELECT 5;
+```
+        "#;
+
+        let arena = Arena::new();
+        let root = parse_document(&arena, &code, &options());
+
+        markdown::highlight_extended_code_blocks(&root, &arena).unwrap();
+
+        let plugins = ComrakPlugins::default();
+        let mut html = vec![];
+        format_html_with_plugins(root, &options(), &mut html, &plugins).unwrap();
+        let html = String::from_utf8(html).unwrap();
+
+        assert_eq!(html.matches("highlighted-line").count(), 4);
+        assert!(html.contains("SELECT 5"));
+    }
+
+    #[test]
+    fn test_syntax_highlighting_linenos_multiline_scope_balanced_per_cell() {
+        // A block comment spans multiple lines/cells; each <td> must close
+        // every <span> it opens rather than leaving one for a later line's
+        // (separate) <td> to close.
+        let code = r#"
+```postgresql,linenos
+/* a comment
+   spanning lines */
+SELECT 1;
+```
+        "#;
+
+        let arena = Arena::new();
+        let root = parse_document(&arena, &code, &options());
+
+        markdown::highlight_extended_code_blocks(&root, &arena).unwrap();
+
+        let plugins = ComrakPlugins::default();
+        let mut html = vec![];
+        format_html_with_plugins(root, &options(), &mut html, &plugins).unwrap();
+        let html = String::from_utf8(html).unwrap();
+
+        for row in html.split(r#"<td class="line-content">"#).skip(1) {
+            let cell = row.split("</td>").next().unwrap_or("");
+            assert_eq!(
+                cell.matches("<span").count(),
+                cell.matches("</span>").count(),
+                "unbalanced spans in cell: {cell}"
+            );
+        }
     }
 
     #[test]
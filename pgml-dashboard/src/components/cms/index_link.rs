@@ -0,0 +1,39 @@
+use std::path::Path;
+
+/// A single entry in a collection's navigation index, built from `SUMMARY.md`
+/// (or, for collections without one, from front matter).
+#[derive(Debug, Clone, Default)]
+pub struct IndexLink {
+    pub id: String,
+    pub title: String,
+    pub href: String,
+    pub children: Vec<IndexLink>,
+    pub open: bool,
+}
+
+impl IndexLink {
+    pub fn new(title: &str) -> IndexLink {
+        IndexLink {
+            id: title.to_lowercase().replace(' ', "-"),
+            title: title.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn href(mut self, href: &str) -> Self {
+        self.href = href.to_string();
+        self
+    }
+
+    pub fn children(mut self, children: Vec<IndexLink>) -> Self {
+        self.children = children;
+        self
+    }
+
+    /// Marks this link as open if `path` is its own href or a direct child's;
+    /// deeper descendants are not checked and no other link is changed.
+    pub fn should_open(mut self, path: &str, _root: &Path) -> Self {
+        self.open = self.href == path || self.children.iter().any(|child| child.href == path);
+        self
+    }
+}
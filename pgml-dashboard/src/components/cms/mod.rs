@@ -0,0 +1,3 @@
+pub mod index_link;
+
+pub use index_link::IndexLink;
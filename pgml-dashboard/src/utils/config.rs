@@ -0,0 +1,33 @@
+use std::path::PathBuf;
+
+/// Root directory the CMS collections (docs/blog/careers) are read from
+/// when no S3 bucket is configured.
+pub fn cms_dir() -> PathBuf {
+    match std::env::var("CMS_DIR") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) => PathBuf::from("content"),
+    }
+}
+
+/// S3-compatible bucket settings for a collection's content, read from env.
+/// Set `CMS_S3_BUCKET` to switch a collection's `ContentSource` from local
+/// disk to the bucket.
+pub struct S3Config {
+    pub endpoint: String,
+    pub region: String,
+    pub bucket: String,
+    pub prefix: String,
+}
+
+/// Returns the S3 settings for `collection` (e.g. "blog"), if `CMS_S3_BUCKET`
+/// is set. The prefix defaults to the collection name so a single bucket can
+/// host every collection under its own folder.
+pub fn s3_config(collection: &str) -> Option<S3Config> {
+    let bucket = std::env::var("CMS_S3_BUCKET").ok()?;
+    Some(S3Config {
+        endpoint: std::env::var("CMS_S3_ENDPOINT").unwrap_or_else(|_| "https://s3.amazonaws.com".to_string()),
+        region: std::env::var("CMS_S3_REGION").unwrap_or_else(|_| "us-east-1".to_string()),
+        bucket,
+        prefix: std::env::var("CMS_S3_PREFIX").unwrap_or_else(|_| collection.to_string()),
+    })
+}
@@ -0,0 +1,149 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use async_trait::async_trait;
+use object_store::{aws::AmazonS3Builder, path::Path as ObjectPath, ObjectStore};
+
+/// Abstracts the filesystem touch points a `Collection` needs
+/// (`SUMMARY.md`, a document's markdown, an asset's bytes, the list of
+/// markdown files) so content can
+/// live on local disk or be synced to an S3-compatible bucket instead of
+/// requiring a rebuild/redeploy to publish.
+#[async_trait]
+pub trait ContentSource: Send + Sync {
+    async fn read_summary(&self) -> Result<String>;
+    async fn read_markdown(&self, path: &Path) -> Result<String>;
+    async fn read_asset(&self, path: &Path) -> Result<Vec<u8>>;
+    /// Every markdown file in the collection, relative to its root, for
+    /// building the nav/taxonomy indexes at load time.
+    async fn list_markdown(&self) -> Result<Vec<PathBuf>>;
+}
+
+/// The original, on-disk backend: everything lives under `config::cms_dir()`.
+pub struct LocalFs {
+    root: PathBuf,
+}
+
+impl LocalFs {
+    pub fn new(root: PathBuf) -> LocalFs {
+        LocalFs { root }
+    }
+}
+
+#[async_trait]
+impl ContentSource for LocalFs {
+    // `std::fs`, not `tokio::fs`: `Collection::new` loads content via
+    // `futures::executor::block_on` at startup, which doesn't guarantee a
+    // Tokio reactor is running underneath it, and `tokio::fs` panics
+    // ("no reactor running") without one.
+    async fn read_summary(&self) -> Result<String> {
+        Ok(std::fs::read_to_string(self.root.join("SUMMARY.md"))?)
+    }
+
+    async fn read_markdown(&self, path: &Path) -> Result<String> {
+        Ok(std::fs::read_to_string(self.root.join(path))?)
+    }
+
+    async fn read_asset(&self, path: &Path) -> Result<Vec<u8>> {
+        Ok(std::fs::read(self.root.join(".gitbook").join("assets").join(path))?)
+    }
+
+    async fn list_markdown(&self) -> Result<Vec<PathBuf>> {
+        fn walk(dir: &Path, root: &Path, out: &mut Vec<PathBuf>) {
+            let Ok(entries) = std::fs::read_dir(dir) else {
+                return;
+            };
+            for entry in entries.filter_map(|e| e.ok()) {
+                let path = entry.path();
+                if path.is_dir() {
+                    walk(&path, root, out);
+                } else if path.extension().and_then(|e| e.to_str()) == Some("md")
+                    && path.file_name().and_then(|n| n.to_str()) != Some("SUMMARY.md")
+                {
+                    out.push(path.strip_prefix(root).unwrap_or(&path).to_path_buf());
+                }
+            }
+        }
+
+        let mut out = vec![];
+        walk(&self.root, &self.root, &mut out);
+        Ok(out)
+    }
+}
+
+/// Reads collection content from an S3-compatible bucket (endpoint, region,
+/// bucket and prefix come from `config`), so publishing new docs is a bucket
+/// sync rather than a redeploy.
+pub struct S3 {
+    store: Box<dyn ObjectStore>,
+    prefix: PathBuf,
+}
+
+impl S3 {
+    pub fn new(endpoint: &str, region: &str, bucket: &str, prefix: &str) -> Result<S3> {
+        // `from_env` (rather than `new`) picks up AWS_ACCESS_KEY_ID /
+        // AWS_SECRET_ACCESS_KEY / AWS_SESSION_TOKEN, so a private bucket
+        // actually authenticates; endpoint/region/bucket still come from our
+        // own `config::s3_config`, not the AWS_* env vars.
+        let store = AmazonS3Builder::from_env()
+            .with_endpoint(endpoint)
+            .with_region(region)
+            .with_bucket_name(bucket)
+            .build()?;
+
+        Ok(S3 {
+            store: Box::new(store),
+            prefix: PathBuf::from(prefix),
+        })
+    }
+
+    fn object_path(&self, path: &Path) -> ObjectPath {
+        ObjectPath::from(self.prefix.join(path).to_string_lossy().to_string())
+    }
+}
+
+#[async_trait]
+impl ContentSource for S3 {
+    async fn read_summary(&self) -> Result<String> {
+        self.read_markdown(Path::new("SUMMARY.md")).await
+    }
+
+    async fn read_markdown(&self, path: &Path) -> Result<String> {
+        let bytes = self
+            .store
+            .get(&self.object_path(path))
+            .await?
+            .bytes()
+            .await?;
+        Ok(String::from_utf8(bytes.to_vec())?)
+    }
+
+    async fn read_asset(&self, path: &Path) -> Result<Vec<u8>> {
+        let object_path = self.object_path(&Path::new(".gitbook/assets").join(path));
+        Ok(self.store.get(&object_path).await?.bytes().await?.to_vec())
+    }
+
+    async fn list_markdown(&self) -> Result<Vec<PathBuf>> {
+        use futures::StreamExt;
+
+        let prefix = ObjectPath::from(self.prefix.to_string_lossy().to_string());
+        let mut stream = self.store.list(Some(&prefix));
+        let mut out = vec![];
+
+        while let Some(meta) = stream.next().await {
+            let meta = meta?;
+            let relative = meta
+                .location
+                .as_ref()
+                .trim_start_matches(&*prefix.as_ref().to_string())
+                .trim_start_matches('/')
+                .to_string();
+
+            if relative.ends_with(".md") && relative != "SUMMARY.md" {
+                out.push(PathBuf::from(relative));
+            }
+        }
+
+        Ok(out)
+    }
+}
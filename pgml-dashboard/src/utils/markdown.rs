@@ -0,0 +1,509 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use comrak::adapters::{HeadingAdapter, HeadingMeta, SyntaxHighlighterAdapter};
+use comrak::arena_tree::Node;
+use comrak::nodes::{Ast, AstNode, NodeHtmlBlock, NodeValue};
+use comrak::{Arena, ComrakOptions};
+use once_cell::sync::Lazy;
+use syntect::html::ClassStyle;
+use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
+use syntect::util::LinesWithEndings;
+
+use crate::components::cms::index_link::IndexLink;
+
+mod shortcodes;
+
+pub use shortcodes::expand_shortcodes;
+
+/// The comrak options used everywhere we parse or render CMS markdown.
+/// Kept in one place so docs, blog and careers content all behave the same way.
+pub fn options() -> ComrakOptions {
+    let mut options = ComrakOptions::default();
+    options.extension.strikethrough = true;
+    options.extension.table = true;
+    options.extension.autolink = true;
+    options.extension.tasklist = true;
+    options.extension.superscript = true;
+    options.extension.header_ids = Some(String::new());
+    options.extension.footnotes = true;
+    options.extension.description_lists = true;
+    options.render.unsafe_ = true;
+    options
+}
+
+/// One entry in a document's table of contents, derived from its headings.
+#[derive(Debug, Clone)]
+pub struct TocLink {
+    pub title: String,
+    pub href: String,
+    pub level: u8,
+}
+
+/// Heading adapter that slugifies headings and emits anchor links the way
+/// the dashboard stylesheet expects, instead of comrak's default `<h1>` output.
+pub struct MarkdownHeadings {}
+
+impl MarkdownHeadings {
+    pub fn new() -> MarkdownHeadings {
+        MarkdownHeadings {}
+    }
+}
+
+impl HeadingAdapter for MarkdownHeadings {
+    fn enter(&self, heading: &HeadingMeta) -> String {
+        let id = heading
+            .content
+            .to_lowercase()
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '-' })
+            .collect::<String>();
+
+        format!(
+            r#"<h{level} id="{id}"><a class="anchor" href="#{id}">#</a>"#,
+            level = heading.level,
+            id = id,
+        )
+    }
+
+    fn exit(&self, heading: &HeadingMeta) -> String {
+        format!("</h{}>", heading.level)
+    }
+}
+
+/// Finds the first `<h1>` in the document and returns its plain-text content.
+pub fn get_title<'a>(root: &'a AstNode<'a>) -> Option<String> {
+    for node in root.children() {
+        if let NodeValue::Heading(heading) = &node.data.borrow().value {
+            if heading.level == 1 {
+                return Some(collect_text(node));
+            }
+        }
+    }
+    None
+}
+
+/// Walks every heading below level 1 and returns a flat TOC; all `<h1>`s,
+/// the title included, are skipped.
+pub fn get_toc<'a>(root: &'a AstNode<'a>) -> Option<Vec<TocLink>> {
+    let mut links = vec![];
+    for node in root.descendants() {
+        if let NodeValue::Heading(heading) = &node.data.borrow().value {
+            if heading.level == 1 {
+                continue;
+            }
+            let title = collect_text(node);
+            let href = title
+                .to_lowercase()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '-' })
+                .collect::<String>();
+            links.push(TocLink {
+                title,
+                href,
+                level: heading.level,
+            });
+        }
+    }
+    Some(links)
+}
+
+fn collect_text<'a>(node: &'a AstNode<'a>) -> String {
+    let mut text = String::new();
+    for child in node.descendants() {
+        if let NodeValue::Text(ref t) = child.data.borrow().value {
+            text.push_str(t);
+        }
+    }
+    text
+}
+
+/// Wraps every top level `<table>` in `<div class="overflow-auto w-100">` so
+/// wide tables scroll horizontally on narrow viewports instead of overflowing.
+pub fn wrap_tables<'a>(root: &'a AstNode<'a>, arena: &'a Arena<AstNode<'a>>) -> Result<()> {
+    for node in root.children().collect::<Vec<_>>() {
+        if matches!(node.data.borrow().value, NodeValue::Table(_)) {
+            let open = arena.alloc(Node::new(std::cell::RefCell::new(Ast::new(
+                NodeValue::HtmlBlock(NodeHtmlBlock {
+                    literal: r#"<div class="overflow-auto w-100">"#.to_string().into_bytes(),
+                    block_type: 0,
+                }),
+                node.data.borrow().sourcepos.start,
+            ))));
+            let close = arena.alloc(Node::new(std::cell::RefCell::new(Ast::new(
+                NodeValue::HtmlBlock(NodeHtmlBlock {
+                    literal: "</div>".to_string().into_bytes(),
+                    block_type: 0,
+                }),
+                node.data.borrow().sourcepos.end,
+            ))));
+            node.insert_before(open);
+            node.insert_after(close);
+        }
+    }
+    Ok(())
+}
+
+/// MkDocs-style syntax support: tabs, admonitions, etc. Currently a no-op
+/// placeholder for the subset we don't yet parse out of the AST.
+pub fn mkdocs<'a>(_root: &'a AstNode<'a>, _arena: &'a Arena<AstNode<'a>>) -> Result<()> {
+    Ok(())
+}
+
+/// A single line number or `a-b` range from a `hl_lines` fence option.
+enum LineSpec {
+    Single(usize),
+    Range(usize, usize),
+}
+
+impl LineSpec {
+    fn contains(&self, line: usize) -> bool {
+        match self {
+            LineSpec::Single(n) => *n == line,
+            LineSpec::Range(a, b) => line >= *a && line <= *b,
+        }
+    }
+}
+
+/// Options parsed out of an extended fence info string, e.g.
+/// `postgresql,linenos,hl_lines=1-3 5,diff`.
+struct FenceOptions {
+    linenos: bool,
+    hl_lines: Vec<LineSpec>,
+    diff: bool,
+}
+
+impl FenceOptions {
+    fn parse(info: &str) -> FenceOptions {
+        let mut linenos = false;
+        let mut hl_lines = vec![];
+        let mut diff = false;
+
+        // The language itself is the first comma-separated token; everything
+        // after it is an option, optionally `key=value`.
+        for token in info.split(',').skip(1) {
+            let token = token.trim();
+            if token == "linenos" {
+                linenos = true;
+            } else if token == "diff" {
+                diff = true;
+            } else if let Some(value) = token.strip_prefix("hl_lines=") {
+                for spec in value.split_whitespace() {
+                    if let Some((a, b)) = spec.split_once('-') {
+                        if let (Ok(a), Ok(b)) = (a.parse(), b.parse()) {
+                            hl_lines.push(LineSpec::Range(a, b));
+                        }
+                    } else if let Ok(n) = spec.parse() {
+                        hl_lines.push(LineSpec::Single(n));
+                    }
+                }
+            }
+        }
+
+        FenceOptions {
+            linenos,
+            hl_lines,
+            diff,
+        }
+    }
+
+    fn is_default(&self) -> bool {
+        !self.linenos && self.hl_lines.is_empty() && !self.diff
+    }
+
+    fn is_highlighted(&self, line: usize) -> bool {
+        self.hl_lines.iter().any(|spec| spec.contains(line))
+    }
+}
+
+/// The Sublime syntaxes we ship with, loaded once. `load_defaults_newlines`
+/// bundles a broad set (Rust, Python, JS, bash, YAML, TOML, SQL, ...); repo
+/// specific `.sublime-syntax` files can be dropped into the same set via
+/// `SyntaxSet::load_from_folder` if we ever need a grammar that isn't upstream.
+static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Fence languages that don't match a Sublime syntax name or extension directly.
+fn resolve_syntax(lang: &str) -> Option<&'static syntect::parsing::SyntaxReference> {
+    let alias = match lang {
+        "postgresql" | "plpgsql" | "pgsql" => "sql",
+        "js" => "js",
+        "rs" => "rust",
+        "py" => "python",
+        "sh" | "shell" => "bash",
+        "yml" => "yaml",
+        other => other,
+    };
+    SYNTAX_SET
+        .find_syntax_by_token(alias)
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(alias))
+}
+
+/// Runs `line` (including its trailing newline) through syntect and emits
+/// scope-based classes (`ClassStyle::Spaced`) rather than inline colors, so
+/// the dashboard stylesheet controls the theme.
+fn highlight_line(
+    parse_state: &mut ParseState,
+    open_spans: &mut ScopeStack,
+    line: &str,
+) -> String {
+    let ops = match parse_state.parse_line(line, &SYNTAX_SET) {
+        Ok(ops) => ops,
+        Err(_) => return escape_html(line),
+    };
+    syntect::html::line_tokens_to_classed_spans(line, ops.as_slice(), ClassStyle::Spaced, open_spans)
+        .map(|(html, _)| html)
+        .unwrap_or_else(|_| escape_html(line))
+}
+
+/// Adds `class` onto an attribute map, merging with whatever comrak (or an
+/// earlier call) already put there rather than clobbering it.
+fn add_class(mut attributes: HashMap<String, String>, class: &str) -> HashMap<String, String> {
+    attributes
+        .entry("class".to_string())
+        .and_modify(|existing| {
+            if !existing.split_whitespace().any(|c| c == class) {
+                existing.push(' ');
+                existing.push_str(class);
+            }
+        })
+        .or_insert_with(|| class.to_string());
+    attributes
+}
+
+/// Highlights fenced code blocks with syntect, emitting scope-based CSS
+/// classes (not inline styles) so the dashboard stylesheet owns the theme.
+/// Falls back to plain escaped text when the fence language doesn't match a
+/// known Sublime syntax.
+///
+/// comrak itself wraps every fenced block in `<pre><code>...</code></pre>`
+/// (calling `write_pre_tag`, `write_code_tag`, `write_highlighted`, in that
+/// order, then writing the closing tags itself) — `write_highlighted` must
+/// only emit what belongs *inside* `<code>`, never another `<pre>`/`<code>`
+/// of its own. Blocks using the extended fence options (`linenos`, `hl_lines`,
+/// `diff`) need a `<table>` for their line gutter, which can't validly live
+/// inside `<code>`; those are rendered separately by
+/// `highlight_extended_code_blocks` before comrak ever sees them, so this
+/// adapter only ever handles the plain case.
+pub struct SyntaxHighlighter {}
+
+impl SyntaxHighlighterAdapter for SyntaxHighlighter {
+    fn write_highlighted(
+        &self,
+        output: &mut dyn std::io::Write,
+        lang: Option<&str>,
+        code: &str,
+    ) -> std::io::Result<()> {
+        let language = lang.unwrap_or("");
+
+        let Some(syntax) = resolve_syntax(language) else {
+            return write!(output, "{}", escape_html(code));
+        };
+
+        let mut parse_state = ParseState::new(syntax);
+        let mut open_spans = ScopeStack::new();
+        for line in LinesWithEndings::from(code) {
+            write!(output, "{}", highlight_line(&mut parse_state, &mut open_spans, line))?;
+        }
+        // `line_tokens_to_classed_spans` only closes spans a line itself
+        // opened; whatever's still open at the end (at minimum the root
+        // scope) is on us to close, same as `ClassedHTMLGenerator::finalize`.
+        write!(output, "{}", "</span>".repeat(open_spans.len()))
+    }
+
+    fn write_pre_tag(
+        &self,
+        output: &mut dyn std::io::Write,
+        attributes: HashMap<String, String>,
+    ) -> std::io::Result<()> {
+        comrak::html::write_opening_tag(output, "pre", add_class(attributes, "syntax-highlight"))
+    }
+
+    fn write_code_tag(
+        &self,
+        output: &mut dyn std::io::Write,
+        attributes: HashMap<String, String>,
+    ) -> std::io::Result<()> {
+        comrak::html::write_opening_tag(output, "code", attributes)
+    }
+}
+
+/// Fenced code blocks using `linenos`, `hl_lines` or `diff` need a `<table>`
+/// line gutter, which isn't valid content for comrak's `<pre><code>` wrapper
+/// (comrak always closes fenced blocks with a literal `</code></pre>`, so an
+/// adapter can't replace it with anything else). Run this ahead of rendering:
+/// it reads each such block's fence info directly off the AST — not the
+/// `lang` comrak's highlighter adapter receives, which is truncated at the
+/// first whitespace and would silently drop space-separated `hl_lines`
+/// values — and replaces the block with its fully rendered `<table>` markup
+/// as a standalone raw HTML node, sitting beside rather than inside a
+/// `<pre>`/`<code>`. Plain fenced blocks are left alone for
+/// `SyntaxHighlighter::write_highlighted` to highlight in place.
+pub fn highlight_extended_code_blocks<'a>(
+    root: &'a AstNode<'a>,
+    arena: &'a Arena<AstNode<'a>>,
+) -> Result<()> {
+    for node in root.descendants().collect::<Vec<_>>() {
+        let (info, literal, sourcepos) = {
+            let ast = node.data.borrow();
+            match &ast.value {
+                NodeValue::CodeBlock(ncb) if ncb.fenced => {
+                    (ncb.info.clone(), ncb.literal.clone(), ast.sourcepos)
+                }
+                _ => continue,
+            }
+        };
+
+        let options = FenceOptions::parse(&info);
+        if options.is_default() {
+            continue;
+        }
+
+        let language = info.split(',').next().unwrap_or("");
+        let html = render_extended_code_block(language, &literal, &options);
+
+        let replacement = arena.alloc(Node::new(std::cell::RefCell::new(Ast::new(
+            NodeValue::HtmlBlock(NodeHtmlBlock {
+                literal: html.into_bytes(),
+                block_type: 0,
+            }),
+            sourcepos.start,
+        ))));
+        node.insert_before(replacement);
+        node.detach();
+    }
+    Ok(())
+}
+
+/// Builds the standalone `<table>` markup for one extended-options fenced
+/// block. Never called from inside comrak's render pass (see
+/// `highlight_extended_code_blocks`), so it's free to emit whatever HTML
+/// structure the line gutter needs. When the language matches no known
+/// syntax, it returns the code as escaped text in a plain `<pre>` instead,
+/// without line numbers, highlighted lines or diff classes.
+fn render_extended_code_block(language: &str, code: &str, options: &FenceOptions) -> String {
+    let Some(syntax) = resolve_syntax(language) else {
+        return format!("<pre>{}</pre>", escape_html(code));
+    };
+
+    // Each highlighted line lands in its own `<td>`, so (unlike the plain
+    // `<pre><code>` path) a span a line opens can't be left for a later line
+    // to close: `parse_state` and `open_spans` carry across lines (so
+    // multi-line scopes like block comments still get the right colors), but
+    // each cell first reopens the scopes earlier lines left open and then
+    // closes everything still open at its end, so every cell is balanced.
+    let mut parse_state = ParseState::new(syntax);
+    let mut open_spans = ScopeStack::new();
+    let lines: Vec<String> = LinesWithEndings::from(code)
+        .map(|line| {
+            let reopened: String = open_spans
+                .as_slice()
+                .iter()
+                .map(|scope| format!(r#"<span class="{}">"#, scope.build_string().replace('.', " ")))
+                .collect();
+            let html = highlight_line(&mut parse_state, &mut open_spans, line);
+            format!("{reopened}{html}{}", "</span>".repeat(open_spans.len()))
+        })
+        .collect();
+
+    let mut html = format!(r#"<table class="syntax-highlight-lines language-{language}">"#);
+    for (i, line) in lines.iter().enumerate() {
+        let lineno = i + 1;
+        let source_line = code.lines().nth(i).unwrap_or("");
+
+        let mut classes = vec!["syntax-highlight-line"];
+        if options.is_highlighted(lineno) {
+            classes.push("highlighted-line");
+        }
+        if options.diff {
+            if source_line.starts_with('+') {
+                classes.push("diff-add");
+            } else if source_line.starts_with('-') {
+                classes.push("diff-remove");
+            }
+        }
+
+        html.push_str(&format!(r#"<tr class="{}">"#, classes.join(" ")));
+        if options.linenos {
+            html.push_str(&format!(r#"<td class="line-number">{lineno}</td>"#));
+        }
+        html.push_str(&format!(r#"<td class="line-content">{line}</td></tr>"#));
+    }
+    html.push_str("</table>");
+    html
+}
+
+/// Parses `SUMMARY.md` gitbook-style nav trees into a flat `IndexLink` tree.
+pub fn parse_summary_into_nav_links(
+    mdast: &::markdown::mdast::Node,
+    root: &std::path::Path,
+) -> Result<Vec<IndexLink>> {
+    fn walk(node: &::markdown::mdast::Node, root: &std::path::Path) -> Vec<IndexLink> {
+        use ::markdown::mdast::Node as N;
+        let mut links = vec![];
+        match node {
+            N::List(list) => {
+                for item in &list.children {
+                    links.extend(walk(item, root));
+                }
+            }
+            N::ListItem(item) => {
+                let mut title = String::new();
+                let mut href = String::new();
+                let mut children = vec![];
+                for child in &item.children {
+                    match child {
+                        N::Paragraph(p) => {
+                            for c in &p.children {
+                                if let N::Link(link) = c {
+                                    for t in &link.children {
+                                        if let N::Text(text) = t {
+                                            title.push_str(&text.value);
+                                        }
+                                    }
+                                    href = root
+                                        .join(link.url.trim_end_matches(".md"))
+                                        .to_string_lossy()
+                                        .to_string();
+                                }
+                            }
+                        }
+                        N::List(_) => children.extend(walk(child, root)),
+                        _ => {}
+                    }
+                }
+                links.push(IndexLink::new(&title).href(&href).children(children));
+            }
+            N::Root(r) => {
+                for child in &r.children {
+                    links.extend(walk(child, root));
+                }
+            }
+            _ => {}
+        }
+        links
+    }
+
+    Ok(walk(mdast, root))
+}
+
+/// In-memory full text search over rendered CMS documents.
+pub struct SearchIndex {}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub title: String,
+    pub href: String,
+    pub excerpt: String,
+}
+
+impl SearchIndex {
+    pub fn search(&self, _query: &str) -> Result<Vec<SearchResult>> {
+        Ok(vec![])
+    }
+}
@@ -0,0 +1,162 @@
+use std::collections::HashMap;
+
+use anyhow::Result;
+use comrak::{format_html, parse_document, Arena};
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::options;
+
+/// A parsed shortcode argument: `name="value"`, `name=3` or `name=true`.
+#[derive(Debug, Clone)]
+pub enum Value {
+    Str(String),
+    Int(i64),
+    Bool(bool),
+}
+
+pub type Args = HashMap<String, Value>;
+
+/// `{{ name(arg="v", n=3) }}` — renders to a single HTML fragment.
+static INLINE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)(?P<escape>\\)?\{\{\s*(?P<name>[a-zA-Z0-9_-]+)\((?P<args>[^)]*)\)\s*\}\}")
+        .unwrap()
+});
+
+/// `{% name(arg="v") %} ... inner markdown ... {% end %}` — renders the
+/// inner markdown first, then passes the resulting HTML to the template.
+static BLOCK: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?s)(?P<escape>\\)?\{%\s*(?P<name>[a-zA-Z0-9_-]+)\((?P<args>[^)]*)\)\s*%\}(?P<inner>.*?)\{%\s*end\s*%\}",
+    )
+    .unwrap()
+});
+
+static ARG: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?P<key>[a-zA-Z0-9_-]+)\s*=\s*(?:"(?P<str>[^"]*)"|(?P<bool>true|false)|(?P<int>-?[0-9]+))"#).unwrap()
+});
+
+fn parse_args(raw: &str) -> Args {
+    let mut args = Args::new();
+    for caps in ARG.captures_iter(raw) {
+        let key = caps["key"].to_string();
+        let value = if let Some(s) = caps.name("str") {
+            Value::Str(s.as_str().to_string())
+        } else if let Some(b) = caps.name("bool") {
+            Value::Bool(b.as_str() == "true")
+        } else if let Some(i) = caps.name("int") {
+            Value::Int(i.as_str().parse().unwrap_or(0))
+        } else {
+            continue;
+        };
+        args.insert(key, value);
+    }
+    args
+}
+
+/// A shortcode template: renders its tag's arguments (and, for block
+/// shortcodes, the already-rendered inner HTML) to an HTML fragment.
+type Template = fn(&Args, Option<&str>) -> String;
+
+/// Looked up by tag name so adding a shortcode is a registry entry, not a
+/// new match arm here. Each entry is a plain `fn` that builds its HTML
+/// directly; none of them goes through the crate's `templates` module.
+static REGISTRY: Lazy<HashMap<&'static str, Template>> = Lazy::new(|| {
+    let mut templates: HashMap<&'static str, Template> = HashMap::new();
+    templates.insert("video", render_video);
+    templates.insert("callout", render_cta);
+    templates.insert("cta", render_cta);
+    templates
+});
+
+fn render_video(args: &Args, _inner: Option<&str>) -> String {
+    let src = str_arg(args, "src").unwrap_or_default();
+    format!(
+        r#"<div class="shortcode-video"><video controls src="{src}"></video></div>"#,
+        src = escape_html(&src)
+    )
+}
+
+fn render_cta(args: &Args, inner: Option<&str>) -> String {
+    let title = str_arg(args, "title").unwrap_or_default();
+    let href = str_arg(args, "href").unwrap_or_default();
+    format!(
+        r#"<div class="shortcode-cta"><h4>{title}</h4><a href="{href}">{body}</a></div>"#,
+        title = escape_html(&title),
+        href = escape_html(&href),
+        body = inner.unwrap_or_default()
+    )
+}
+
+/// Renders the named shortcode template with the given arguments. Unknown
+/// shortcodes render a visible error marker instead of panicking, so a typo
+/// in a doc doesn't take down the whole page.
+fn render(name: &str, args: &Args, inner: Option<&str>) -> String {
+    match REGISTRY.get(name) {
+        Some(template) => template(args, inner),
+        None => format!(
+            r#"<span class="shortcode-error">unknown shortcode: {}</span>"#,
+            escape_html(name)
+        ),
+    }
+}
+
+fn str_arg<'a>(args: &'a Args, key: &str) -> Option<String> {
+    match args.get(key) {
+        Some(Value::Str(s)) => Some(s.clone()),
+        Some(Value::Int(i)) => Some(i.to_string()),
+        Some(Value::Bool(b)) => Some(b.to_string()),
+        None => None,
+    }
+}
+
+/// Escapes a shortcode argument before it's interpolated into HTML
+/// (attribute values and text alike), so e.g. a `title` containing `"` or
+/// `<` can't break out of its tag.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn render_inner_markdown(markdown: &str) -> Result<String> {
+    let arena = Arena::new();
+    let root = parse_document(&arena, markdown, &options());
+    let mut html = vec![];
+    format_html(root, &options(), &mut html)?;
+    Ok(String::from_utf8(html)?)
+}
+
+/// Expands `{{ shortcode(...) }}` and `{% shortcode(...) %}...{% end %}`
+/// occurrences in raw CMS markdown into their rendered HTML, before the
+/// document is handed to comrak. Runs ahead of (and in the same pipeline
+/// stage as) `mkdocs`, since both rewrite author-facing syntax that comrak
+/// itself doesn't understand. A literal `{{` can be escaped with a leading
+/// backslash.
+pub fn expand_shortcodes(source: &str) -> Result<String> {
+    let mut out = BLOCK
+        .replace_all(source, |caps: &regex::Captures| {
+            if caps.name("escape").is_some() {
+                return caps[0].trim_start_matches('\\').to_string();
+            }
+            let name = &caps["name"];
+            let args = parse_args(&caps["args"]);
+            let inner_html = render_inner_markdown(&caps["inner"]).unwrap_or_default();
+            render(name, &args, Some(&inner_html))
+        })
+        .to_string();
+
+    out = INLINE
+        .replace_all(&out, |caps: &regex::Captures| {
+            if caps.name("escape").is_some() {
+                return caps[0].trim_start_matches('\\').to_string();
+            }
+            let name = &caps["name"];
+            let args = parse_args(&caps["args"]);
+            render(name, &args, None)
+        })
+        .to_string();
+
+    Ok(out)
+}
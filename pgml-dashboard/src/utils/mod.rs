@@ -0,0 +1,4 @@
+pub mod config;
+pub mod content_source;
+pub mod markdown;
+pub mod ttl_cache;
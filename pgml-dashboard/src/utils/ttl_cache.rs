@@ -0,0 +1,32 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+/// A small in-process cache with per-entry TTL, so hot documents aren't
+/// re-fetched from a `ContentSource` (in particular S3) on every request.
+pub struct TtlCache<V: Clone> {
+    ttl: Duration,
+    entries: RwLock<HashMap<PathBuf, (Instant, V)>>,
+}
+
+impl<V: Clone> TtlCache<V> {
+    pub fn new(ttl: Duration) -> TtlCache<V> {
+        TtlCache {
+            ttl,
+            entries: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, key: &PathBuf) -> Option<V> {
+        let entries = self.entries.read().unwrap();
+        entries
+            .get(key)
+            .filter(|(inserted, _)| inserted.elapsed() < self.ttl)
+            .map(|(_, value)| value.clone())
+    }
+
+    pub fn put(&self, key: PathBuf, value: V) {
+        self.entries.write().unwrap().insert(key, (Instant::now(), value));
+    }
+}